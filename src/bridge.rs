@@ -0,0 +1,361 @@
+/// `LockForTransfer` locks tokens on this chain and hands the relayer
+/// contract (`Constants::core_bridge`) a transfer payload to carry to the
+/// destination chain; `CompleteTransfer` mints the corresponding amount once
+/// a trusted emitter's message is observed here.
+use cosmwasm_std::{
+    log, to_binary, Api, Binary, CanonicalAddr, CosmosMsg, Env, Extern, HandleResponse, HumanAddr,
+    Querier, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{
+    is_transfer_completed, read_bridge_contract, set_transfer_completed, store_transfer, Balances,
+    Config,
+};
+
+/// The payload carried to the destination chain: enough for a relayer to
+/// reconstruct and deliver the matching `CompleteTransfer` there.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct TransferPayload {
+    pub token_address: HumanAddr,
+    pub amount: Uint128,
+    pub recipient_chain: u16,
+    pub recipient: Binary,
+    pub nonce: u32,
+}
+
+/// The message this contract sends to `Constants::core_bridge` so the
+/// payload gets relayed off-chain, mirroring how `Snip20Msg` lets this
+/// contract call out to a receiver in `receiver.rs`.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub enum CoreBridgeHandleMsg {
+    PostMessage { payload: Binary },
+}
+
+/// A `CompleteTransfer` message as relayed from `emitter_chain`, already
+/// parsed out of the guardian-observed payload. `emitter_address` is the
+/// *foreign*-chain address that produced this message — the same 32-byte
+/// representation `RegisterBridgeContract` stores in
+/// `PREFIX_BRIDGE_CONTRACTS` — not the local `message.sender` of whichever
+/// relayer happens to submit this handle call.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct CompleteTransferMsg {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub payload: TransferPayload,
+}
+
+/// Handles `HandleMsg::LockForTransfer`: debits `env.message.sender` the
+/// same way a regular transfer would, then relays the transfer payload out
+/// through `core_bridge`.
+pub fn try_lock_for_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    recipient_chain: u16,
+    recipient: Binary,
+    amount: Uint128,
+    nonce: u32,
+) -> StdResult<HandleResponse> {
+    let sender = deps.api.canonical_address(&env.message.sender)?;
+
+    let mut balances = Balances::from_storage(&mut deps.storage);
+    let sender_balance = balances.balance(&sender);
+    let amount_raw = amount.u128();
+    if sender_balance < amount_raw {
+        return Err(StdError::generic_err(format!(
+            "insufficient funds to lock: balance={}, required={}",
+            sender_balance, amount_raw
+        )));
+    }
+    balances.set_account_balance(&sender, sender_balance - amount_raw);
+
+    // Locking burns the tokens out of local circulation in exchange for the
+    // mint `CompleteTransfer` performs on the destination chain; without
+    // this, `total_supply` would drift upward relative to the sum of
+    // balances on every round trip through the bridge.
+    let mut config = Config::from_storage(&mut deps.storage);
+    let total_supply = config.total_supply() - amount_raw;
+    config.set_total_supply(total_supply);
+
+    // No custody account holds these tokens — they're burned, not
+    // transferred — so the receiver of this tx record is `sender` itself
+    // rather than the contract's own address; recording a receiver whose
+    // balance was never credited would leave the tx history contradicting
+    // the balances it's meant to describe.
+    let constants = Config::from_storage(&mut deps.storage).constants()?;
+    store_transfer(
+        &mut deps.storage,
+        &sender,
+        &sender,
+        &sender,
+        amount,
+        constants.symbol.clone(),
+    )?;
+
+    let payload = TransferPayload {
+        token_address: env.contract.address.clone(),
+        amount,
+        recipient_chain,
+        recipient,
+        nonce,
+    };
+
+    let messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: constants.core_bridge,
+        callback_code_hash: env.contract_code_hash.clone(),
+        msg: to_binary(&CoreBridgeHandleMsg::PostMessage {
+            payload: to_binary(&payload)?,
+        })?,
+        send: vec![],
+    })];
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![
+            log("action", "lock_for_transfer"),
+            log("recipient_chain", recipient_chain.to_string()),
+            log("amount", amount),
+            log("nonce", nonce.to_string()),
+        ],
+        data: Some(to_binary(&payload)?),
+    })
+}
+
+/// Handles `HandleMsg::CompleteTransfer`: mints `payload.amount` into the
+/// recipient's balance, provided `emitter_address` is the trusted emitter
+/// registered for `emitter_chain` and `sequence` hasn't already been
+/// completed. The relayer that happens to submit this handle call
+/// (`env.message.sender`) is not itself trusted — only the foreign-chain
+/// emitter the message claims to come from is.
+pub fn try_complete_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    msg: CompleteTransferMsg,
+) -> StdResult<HandleResponse> {
+    let CompleteTransferMsg {
+        emitter_chain,
+        emitter_address,
+        sequence,
+        payload,
+    } = msg;
+
+    let trusted_emitter = read_bridge_contract(&deps.storage, emitter_chain).ok_or_else(|| {
+        StdError::generic_err(format!(
+            "no bridge contract registered for chain {}",
+            emitter_chain
+        ))
+    })?;
+    let claimed_emitter = CanonicalAddr::from(emitter_address.to_vec());
+    if claimed_emitter != trusted_emitter {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if is_transfer_completed(&deps.storage, emitter_chain, sequence) {
+        return Err(StdError::generic_err("transfer already completed"));
+    }
+    set_transfer_completed(&mut deps.storage, emitter_chain, sequence);
+
+    let recipient = deps
+        .api
+        .human_address(&CanonicalAddr::from(payload.recipient.as_slice().to_vec()))?;
+    let recipient_raw = deps.api.canonical_address(&recipient)?;
+
+    let mut balances = Balances::from_storage(&mut deps.storage);
+    let credited = balances.balance(&recipient_raw) + payload.amount.u128();
+    balances.set_account_balance(&recipient_raw, credited);
+
+    let mut config = Config::from_storage(&mut deps.storage);
+    let total_supply = config.total_supply() + payload.amount.u128();
+    config.set_total_supply(total_supply);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "complete_transfer"),
+            log("emitter_chain", emitter_chain.to_string()),
+            log("sequence", sequence.to_string()),
+            log("amount", payload.amount),
+        ],
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use crate::state::{write_bridge_contract, Constants};
+
+    fn set_up_constants<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        total_supply: u128,
+    ) {
+        let mut config = Config::from_storage(&mut deps.storage);
+        config
+            .set_constants(&Constants {
+                name: "Secret SCRT".to_string(),
+                admin: HumanAddr("admin".to_string()),
+                symbol: "SSCRT".to_string(),
+                decimals: 6,
+                prng_seed: vec![0u8; 32],
+                total_supply_is_public: true,
+                core_bridge: HumanAddr("core-bridge".to_string()),
+            })
+            .unwrap();
+        config.set_total_supply(total_supply);
+    }
+
+    #[test]
+    fn lock_for_transfer_debits_balance_and_supply_together() {
+        let mut deps = mock_dependencies(20, &[]);
+        set_up_constants(&mut deps, 1_000);
+        let sender = deps
+            .api
+            .canonical_address(&HumanAddr("alice".to_string()))
+            .unwrap();
+        Balances::from_storage(&mut deps.storage).set_account_balance(&sender, 1_000);
+
+        try_lock_for_transfer(
+            &mut deps,
+            mock_env("alice", &[]),
+            2,
+            Binary::from(vec![7u8; 32]),
+            Uint128(400),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Balances::from_storage(&mut deps.storage).balance(&sender),
+            600
+        );
+        assert_eq!(Config::from_storage(&mut deps.storage).total_supply(), 600);
+    }
+
+    #[test]
+    fn complete_transfer_round_trip_restores_total_supply() {
+        let mut deps = mock_dependencies(20, &[]);
+        set_up_constants(&mut deps, 1_000);
+        let alice = deps
+            .api
+            .canonical_address(&HumanAddr("alice".to_string()))
+            .unwrap();
+        Balances::from_storage(&mut deps.storage).set_account_balance(&alice, 1_000);
+
+        try_lock_for_transfer(
+            &mut deps,
+            mock_env("alice", &[]),
+            2,
+            Binary::from(alice.as_slice().to_vec()),
+            Uint128(400),
+            0,
+        )
+        .unwrap();
+        assert_eq!(Config::from_storage(&mut deps.storage).total_supply(), 600);
+
+        let emitter_address = [9u8; 32];
+        write_bridge_contract(
+            &mut deps.storage,
+            2,
+            &CanonicalAddr::from(emitter_address.to_vec()),
+        );
+
+        try_complete_transfer(
+            &mut deps,
+            mock_env("relayer", &[]),
+            CompleteTransferMsg {
+                emitter_chain: 2,
+                emitter_address,
+                sequence: 1,
+                payload: TransferPayload {
+                    token_address: HumanAddr("this-contract".to_string()),
+                    amount: Uint128(400),
+                    recipient_chain: 2,
+                    recipient: Binary::from(alice.as_slice().to_vec()),
+                    nonce: 0,
+                },
+            },
+        )
+        .unwrap();
+
+        assert_eq!(Config::from_storage(&mut deps.storage).total_supply(), 1_000);
+        assert_eq!(
+            Balances::from_storage(&mut deps.storage).balance(&alice),
+            1_000
+        );
+    }
+
+    #[test]
+    fn complete_transfer_rejects_untrusted_emitter() {
+        let mut deps = mock_dependencies(20, &[]);
+        set_up_constants(&mut deps, 1_000);
+        write_bridge_contract(
+            &mut deps.storage,
+            2,
+            &CanonicalAddr::from([9u8; 32].to_vec()),
+        );
+        let alice = deps
+            .api
+            .canonical_address(&HumanAddr("alice".to_string()))
+            .unwrap();
+
+        let err = try_complete_transfer(
+            &mut deps,
+            mock_env("relayer", &[]),
+            CompleteTransferMsg {
+                emitter_chain: 2,
+                emitter_address: [1u8; 32],
+                sequence: 1,
+                payload: TransferPayload {
+                    token_address: HumanAddr("this-contract".to_string()),
+                    amount: Uint128(400),
+                    recipient_chain: 2,
+                    recipient: Binary::from(alice.as_slice().to_vec()),
+                    nonce: 0,
+                },
+            },
+        )
+        .unwrap_err();
+
+        match err {
+            StdError::Unauthorized { .. } => {}
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn complete_transfer_rejects_replay() {
+        let mut deps = mock_dependencies(20, &[]);
+        set_up_constants(&mut deps, 1_000);
+        let emitter_address = [9u8; 32];
+        write_bridge_contract(
+            &mut deps.storage,
+            2,
+            &CanonicalAddr::from(emitter_address.to_vec()),
+        );
+        let alice = deps
+            .api
+            .canonical_address(&HumanAddr("alice".to_string()))
+            .unwrap();
+        let msg = CompleteTransferMsg {
+            emitter_chain: 2,
+            emitter_address,
+            sequence: 1,
+            payload: TransferPayload {
+                token_address: HumanAddr("this-contract".to_string()),
+                amount: Uint128(400),
+                recipient_chain: 2,
+                recipient: Binary::from(alice.as_slice().to_vec()),
+                nonce: 0,
+            },
+        };
+
+        try_complete_transfer(&mut deps, mock_env("relayer", &[]), msg.clone()).unwrap();
+        let err = try_complete_transfer(&mut deps, mock_env("relayer", &[]), msg).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("already completed")),
+            other => panic!("expected already-completed error, got {:?}", other),
+        }
+    }
+}
@@ -15,8 +15,55 @@ use crate::receiver::Snip20ReceiveMsg;
 use crate::state::{
     get_receiver_hash, get_transfers, read_allowance, read_viewing_key, set_receiver_hash,
     store_transfer, write_allowance, write_viewing_key, Balances, Config, Constants,
-    ReadonlyBalances, ReadonlyConfig,
+    ContractVersion, ReadonlyBalances, ReadonlyConfig, CONTRACT,
 };
 use crate::viewing_key::{ViewingKey, VIEWING_KEY_SIZE};
 
-/// We make sure that responses from `handle` are padded to a multiple of this siz
\ No newline at end of file
+/// We make sure that responses from `handle` are padded to a multiple of this siz
+
+/// cw2-style identity for this contract, checked (and bumped) by `migrate`.
+const CONTRACT_NAME: &str = "secret-secret";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Stamps the current `ContractVersion`. `init` must call this after saving
+/// its own state, so `migrate`'s guard has a baseline to check an upgrade
+/// against instead of treating every prior instance as untrusted.
+pub fn stamp_contract_version<S: Storage>(storage: &mut S) -> StdResult<()> {
+    CONTRACT.save(
+        storage,
+        &ContractVersion {
+            contract: CONTRACT_NAME.to_string(),
+            version: CONTRACT_VERSION.to_string(),
+        },
+    )
+}
+
+/// The `migrate` entry point (wired up alongside `init`/`handle`/`query` in
+/// the `wasm` module of `lib.rs`): refuses to migrate an instance that
+/// `init` never stamped a `ContractVersion` on or that belongs to a
+/// different contract, backfills any `Constants` fields that post-date the
+/// instance being upgraded, then bumps the stored version. This is the
+/// supported way to patch the contract without redeploying and losing
+/// balances and transaction history.
+pub fn migrate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    _msg: crate::msg::MigrateMsg,
+) -> StdResult<cosmwasm_std::MigrateResponse> {
+    let previous = CONTRACT.may_load(&deps.storage)?.ok_or_else(|| {
+        StdError::generic_err(
+            "no contract version on record; refusing to migrate an instance init never stamped",
+        )
+    })?;
+    if previous.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(format!(
+            "cannot migrate contract {} into {}",
+            previous.contract, CONTRACT_NAME
+        )));
+    }
+
+    Config::from_storage(&mut deps.storage).migrate_constants()?;
+    stamp_contract_version(&mut deps.storage)?;
+
+    Ok(cosmwasm_std::MigrateResponse::default())
+}
\ No newline at end of file
@@ -0,0 +1,487 @@
+/// Guardian-set governance, replacing the single `Constants::admin` signer
+/// for privileged actions with a quorum-signed VAA.
+use k256::ecdsa::recoverable;
+use k256::ecdsa::Signature as EcdsaSignature;
+use sha3::{Digest, Keccak256};
+
+use cosmwasm_std::{
+    Api, CanonicalAddr, Env, Extern, HandleResponse, Querier, StdError, StdResult, Storage,
+};
+
+use crate::msg::ContractStatusLevel;
+use crate::state::{
+    is_transfer_completed, read_bridge_contract, set_transfer_completed, write_bridge_contract,
+    Config, GuardianSet,
+};
+
+/// The reserved `chain_id` under which this contract's trusted governance
+/// emitter is registered in `PREFIX_BRIDGE_CONTRACTS` (via the same
+/// `RegisterBridgeContract` governance action used for real bridge
+/// emitters) — distinct from any real foreign chain id a transfer could
+/// come from.
+pub const GOVERNANCE_CHAIN_ID: u16 = 0;
+
+const GUARDIAN_INDEX_LEN: usize = 1;
+const SIGNATURE_RS_LEN: usize = 64;
+const RECOVERY_ID_LEN: usize = 1;
+const BODY_HEADER_LEN: usize = 4 + 4 + 2 + 32 + 8; // timestamp, nonce, emitter_chain, emitter_address, sequence
+
+struct GuardianSignature {
+    guardian_index: u8,
+    rs: [u8; SIGNATURE_RS_LEN],
+    recovery_id: u8,
+}
+
+pub struct VaaBody {
+    pub timestamp: u32,
+    pub nonce: u32,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+}
+
+/// The governance action a verified VAA's payload decodes to.
+pub enum GovernanceAction {
+    SetContractStatus { status: ContractStatusLevel },
+    RegisterBridgeContract { chain_id: u16, emitter: CanonicalAddr },
+    UpdateGuardianSet { guardian_set: GuardianSet },
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(bytes);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.finalize());
+    out
+}
+
+fn guardian_address(uncompressed_pubkey: &[u8]) -> [u8; 20] {
+    // Drop the leading `0x04` tag before hashing, same as deriving an
+    // Ethereum-style address from a secp256k1 public key.
+    let hash = keccak256(&uncompressed_pubkey[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Parses and verifies a VAA against `guardian_set`: checks that the
+/// guardian set index matches, that guardian indices are strictly
+/// increasing (no duplicate signers), and that at least
+/// `floor(len * 2 / 3) + 1` signatures recover to addresses in the set.
+/// Returns the verified body on success.
+pub fn verify_vaa(guardian_set: &GuardianSet, vaa: &[u8]) -> StdResult<VaaBody> {
+    let mut pos = 0usize;
+    let _version = take_u8(vaa, &mut pos)?;
+    let guardian_set_index = take_u32(vaa, &mut pos)?;
+    if guardian_set_index != guardian_set.index {
+        return Err(StdError::generic_err(
+            "VAA was signed by a different guardian set",
+        ));
+    }
+
+    let signature_count = take_u8(vaa, &mut pos)? as usize;
+    let mut signatures = Vec::with_capacity(signature_count);
+    for _ in 0..signature_count {
+        let guardian_index = take_u8(vaa, &mut pos)?;
+        let mut rs = [0u8; SIGNATURE_RS_LEN];
+        rs.copy_from_slice(take_bytes(vaa, &mut pos, SIGNATURE_RS_LEN)?);
+        let recovery_id = take_u8(vaa, &mut pos)?;
+        signatures.push(GuardianSignature {
+            guardian_index,
+            rs,
+            recovery_id,
+        });
+    }
+
+    let body_bytes = &vaa[pos..];
+    if body_bytes.len() < BODY_HEADER_LEN {
+        return Err(StdError::generic_err("VAA body is too short"));
+    }
+    let digest = keccak256(&keccak256(body_bytes));
+
+    let mut last_index: Option<u8> = None;
+    let mut valid_signatures = 0usize;
+    for signature in &signatures {
+        if let Some(last) = last_index {
+            if signature.guardian_index <= last {
+                return Err(StdError::generic_err(
+                    "guardian indices must be strictly increasing",
+                ));
+            }
+        }
+        last_index = Some(signature.guardian_index);
+
+        let expected = guardian_set
+            .addresses
+            .get(signature.guardian_index as usize)
+            .ok_or_else(|| StdError::generic_err("signature references an unknown guardian"))?;
+
+        let recovered = recover_guardian_address(&digest, signature)?;
+        if &recovered != expected {
+            return Err(StdError::generic_err(
+                "signature does not match the claimed guardian",
+            ));
+        }
+        valid_signatures += 1;
+    }
+
+    let required = guardian_set.addresses.len() * 2 / 3 + 1;
+    if valid_signatures < required {
+        return Err(StdError::generic_err(format!(
+            "quorum not met: got {} valid signatures, need {}",
+            valid_signatures, required
+        )));
+    }
+
+    let mut body_pos = 0usize;
+    let timestamp = take_u32(body_bytes, &mut body_pos)?;
+    let nonce = take_u32(body_bytes, &mut body_pos)?;
+    let emitter_chain = take_u16(body_bytes, &mut body_pos)?;
+    let mut emitter_address = [0u8; 32];
+    emitter_address.copy_from_slice(take_bytes(body_bytes, &mut body_pos, 32)?);
+    let sequence = take_u64(body_bytes, &mut body_pos)?;
+    let payload = body_bytes[body_pos..].to_vec();
+
+    Ok(VaaBody {
+        timestamp,
+        nonce,
+        emitter_chain,
+        emitter_address,
+        sequence,
+        payload,
+    })
+}
+
+fn recover_guardian_address(digest: &[u8; 32], signature: &GuardianSignature) -> StdResult<[u8; 20]> {
+    let sig = EcdsaSignature::from_bytes(&signature.rs)
+        .map_err(|_| StdError::generic_err("malformed guardian signature"))?;
+    let recovery_id = recoverable::Id::new(signature.recovery_id)
+        .map_err(|_| StdError::generic_err("invalid recovery id"))?;
+    let recoverable_sig = recoverable::Signature::new(&sig, recovery_id)
+        .map_err(|_| StdError::generic_err("malformed guardian signature"))?;
+    let recovered_key = recoverable_sig
+        .recover_verifying_key_from_digest_bytes(digest.into())
+        .map_err(|_| StdError::generic_err("could not recover guardian public key"))?;
+
+    Ok(guardian_address(recovered_key.to_encoded_point(false).as_bytes()))
+}
+
+/// Decodes a verified VAA's payload into the governance action it requests.
+/// Byte 0 is the action tag; the remaining bytes are the action's fields,
+/// big-endian, in the same style `state.rs` already uses for its own keys.
+pub fn decode_action(payload: &[u8]) -> StdResult<GovernanceAction> {
+    let mut pos = 0usize;
+    let action_id = take_u8(payload, &mut pos)?;
+    match action_id {
+        1 => {
+            let status = crate::msg::u8_to_status_level(take_u8(payload, &mut pos)?)?;
+            Ok(GovernanceAction::SetContractStatus { status })
+        }
+        2 => {
+            let chain_id = take_u16(payload, &mut pos)?;
+            let emitter = CanonicalAddr::from(take_bytes(payload, &mut pos, 32)?.to_vec());
+            Ok(GovernanceAction::RegisterBridgeContract { chain_id, emitter })
+        }
+        3 => {
+            let index = take_u32(payload, &mut pos)?;
+            let guardian_count = take_u8(payload, &mut pos)? as usize;
+            let mut addresses = Vec::with_capacity(guardian_count);
+            for _ in 0..guardian_count {
+                let mut address = [0u8; 20];
+                address.copy_from_slice(take_bytes(payload, &mut pos, 20)?);
+                addresses.push(address);
+            }
+            Ok(GovernanceAction::UpdateGuardianSet {
+                guardian_set: GuardianSet { index, addresses },
+            })
+        }
+        other => Err(StdError::generic_err(format!(
+            "unknown governance action id {}",
+            other
+        ))),
+    }
+}
+
+pub(crate) fn take_u8(bytes: &[u8], pos: &mut usize) -> StdResult<u8> {
+    let value = *bytes
+        .get(*pos)
+        .ok_or_else(|| StdError::generic_err("unexpected end of VAA"))?;
+    *pos += 1;
+    Ok(value)
+}
+
+fn take_u16(bytes: &[u8], pos: &mut usize) -> StdResult<u16> {
+    let slice = take_bytes(bytes, pos, 2)?;
+    Ok(u16::from_be_bytes([slice[0], slice[1]]))
+}
+
+fn take_u32(bytes: &[u8], pos: &mut usize) -> StdResult<u32> {
+    let slice = take_bytes(bytes, pos, 4)?;
+    Ok(u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+fn take_u64(bytes: &[u8], pos: &mut usize) -> StdResult<u64> {
+    let slice = take_bytes(bytes, pos, 8)?;
+    let mut array = [0u8; 8];
+    array.copy_from_slice(slice);
+    Ok(u64::from_be_bytes(array))
+}
+
+pub(crate) fn take_bytes<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> StdResult<&'a [u8]> {
+    let end = *pos + len;
+    let slice = bytes
+        .get(*pos..end)
+        .ok_or_else(|| StdError::generic_err("unexpected end of VAA"))?;
+    *pos = end;
+    Ok(slice)
+}
+
+/// Handles `HandleMsg::SubmitGovernanceAction`: verifies `vaa` against the
+/// stored guardian set, checks it was actually emitted by the registered
+/// governance emitter, rejects replays of an already-consumed
+/// `(emitter_chain, sequence)`, and applies the governance action it
+/// carries.
+pub fn try_submit_governance_action<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    vaa: &[u8],
+) -> StdResult<HandleResponse> {
+    let guardian_set = Config::from_storage(&mut deps.storage).guardian_set()?;
+    let body = verify_vaa(&guardian_set, vaa)?;
+
+    let trusted_emitter = read_bridge_contract(&deps.storage, GOVERNANCE_CHAIN_ID)
+        .ok_or_else(|| StdError::generic_err("no governance emitter registered"))?;
+    let claimed_emitter = CanonicalAddr::from(body.emitter_address.to_vec());
+    if body.emitter_chain != GOVERNANCE_CHAIN_ID || claimed_emitter != trusted_emitter {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if is_transfer_completed(&deps.storage, body.emitter_chain, body.sequence) {
+        return Err(StdError::generic_err("governance action already executed"));
+    }
+    set_transfer_completed(&mut deps.storage, body.emitter_chain, body.sequence);
+
+    let action = decode_action(&body.payload)?;
+
+    match action {
+        GovernanceAction::SetContractStatus { status } => {
+            Config::from_storage(&mut deps.storage).set_contract_status(status);
+        }
+        GovernanceAction::RegisterBridgeContract { chain_id, emitter } => {
+            write_bridge_contract(&mut deps.storage, chain_id, &emitter);
+        }
+        GovernanceAction::UpdateGuardianSet { guardian_set } => {
+            Config::from_storage(&mut deps.storage).set_guardian_set(&guardian_set)?;
+        }
+    }
+
+    Ok(HandleResponse::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    fn guardian_keypair() -> (SigningKey, [u8; 20]) {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let address = guardian_address(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        );
+        (signing_key, address)
+    }
+
+    /// Signs `digest` (already double-keccak-hashed, as `verify_vaa` expects)
+    /// and returns the `(r||s, recovery_id)` pair the VAA wire format stores
+    /// per signature.
+    fn sign_digest(signing_key: &SigningKey, digest: &[u8; 32]) -> ([u8; 64], u8) {
+        let signature: recoverable::Signature = signing_key.sign_prehash(&digest[..]).unwrap();
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(signature.signature().as_ref());
+        (rs, signature.recovery_id().into())
+    }
+
+    fn build_vaa(
+        guardian_set_index: u32,
+        signatures: &[(u8, [u8; 64], u8)],
+        body: &[u8],
+    ) -> Vec<u8> {
+        let mut vaa = Vec::new();
+        vaa.push(1u8); // version
+        vaa.extend_from_slice(&guardian_set_index.to_be_bytes());
+        vaa.push(signatures.len() as u8);
+        for (guardian_index, rs, recovery_id) in signatures {
+            vaa.push(*guardian_index);
+            vaa.extend_from_slice(rs);
+            vaa.push(*recovery_id);
+        }
+        vaa.extend_from_slice(body);
+        vaa
+    }
+
+    fn build_body(emitter_chain: u16, emitter_address: [u8; 32], sequence: u64, payload: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_be_bytes()); // timestamp
+        body.extend_from_slice(&0u32.to_be_bytes()); // nonce
+        body.extend_from_slice(&emitter_chain.to_be_bytes());
+        body.extend_from_slice(&emitter_address);
+        body.extend_from_slice(&sequence.to_be_bytes());
+        body.extend_from_slice(payload);
+        body
+    }
+
+    fn signed_vaa(
+        guardians: &[SigningKey],
+        signer_indices: &[u8],
+        body: &[u8],
+    ) -> Vec<u8> {
+        let digest = keccak256(&keccak256(body));
+        let signatures: Vec<(u8, [u8; 64], u8)> = signer_indices
+            .iter()
+            .map(|&index| {
+                let (rs, recovery_id) = sign_digest(&guardians[index as usize], &digest);
+                (index, rs, recovery_id)
+            })
+            .collect();
+        build_vaa(0, &signatures, body)
+    }
+
+    #[test]
+    fn quorum_met_with_three_of_three_signatures_succeeds() {
+        let guardians: Vec<(SigningKey, [u8; 20])> =
+            (0..3).map(|_| guardian_keypair()).collect();
+        let guardian_set = GuardianSet {
+            index: 0,
+            addresses: guardians.iter().map(|(_, addr)| *addr).collect(),
+        };
+        let keys: Vec<SigningKey> = guardians.into_iter().map(|(key, _)| key).collect();
+
+        // Quorum for three guardians is 3 * 2 / 3 + 1 = 3 signatures.
+        let body = build_body(2, [1u8; 32], 1, &[]);
+        let vaa = signed_vaa(&keys, &[0, 1, 2], &body);
+
+        verify_vaa(&guardian_set, &vaa).unwrap();
+    }
+
+    #[test]
+    fn quorum_not_met_with_one_of_three_signatures_fails() {
+        let guardians: Vec<(SigningKey, [u8; 20])> =
+            (0..3).map(|_| guardian_keypair()).collect();
+        let guardian_set = GuardianSet {
+            index: 0,
+            addresses: guardians.iter().map(|(_, addr)| *addr).collect(),
+        };
+        let keys: Vec<SigningKey> = guardians.into_iter().map(|(key, _)| key).collect();
+
+        let body = build_body(2, [1u8; 32], 1, &[]);
+        let vaa = signed_vaa(&keys, &[0], &body);
+
+        let err = verify_vaa(&guardian_set, &vaa).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("quorum not met")),
+            other => panic!("expected quorum error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicate_guardian_index_rejected() {
+        let guardians: Vec<(SigningKey, [u8; 20])> =
+            (0..3).map(|_| guardian_keypair()).collect();
+        let guardian_set = GuardianSet {
+            index: 0,
+            addresses: guardians.iter().map(|(_, addr)| *addr).collect(),
+        };
+        let keys: Vec<SigningKey> = guardians.into_iter().map(|(key, _)| key).collect();
+
+        let body = build_body(2, [1u8; 32], 1, &[]);
+        // Same guardian index twice: the second entry is rejected for being
+        // out of order before its signature is ever recovered.
+        let vaa = signed_vaa(&keys, &[0, 0], &body);
+
+        let err = verify_vaa(&guardian_set, &vaa).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => {
+                assert!(msg.contains("strictly increasing"))
+            }
+            other => panic!("expected ordering error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bad_recovery_id_rejected() {
+        let guardians: Vec<(SigningKey, [u8; 20])> =
+            (0..3).map(|_| guardian_keypair()).collect();
+        let guardian_set = GuardianSet {
+            index: 0,
+            addresses: guardians.iter().map(|(_, addr)| *addr).collect(),
+        };
+        let keys: Vec<SigningKey> = guardians.into_iter().map(|(key, _)| key).collect();
+
+        let body = build_body(2, [1u8; 32], 1, &[]);
+        let digest = keccak256(&keccak256(&body));
+        let (rs, recovery_id) = sign_digest(&keys[0], &digest);
+        let flipped_recovery_id = 1 - recovery_id;
+        let vaa = build_vaa(0, &[(0, rs, flipped_recovery_id)], &body);
+
+        // Flipping the recovery id recovers a different (wrong) public key,
+        // so this must fail the guardian-address comparison rather than
+        // silently accept an unrelated signer.
+        let err = verify_vaa(&guardian_set, &vaa).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => assert!(
+                msg.contains("does not match the claimed guardian")
+                    || msg.contains("quorum not met")
+            ),
+            other => panic!("expected a recovery-mismatch error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn submit_governance_action_rejects_replay() {
+        let guardians: Vec<(SigningKey, [u8; 20])> =
+            (0..3).map(|_| guardian_keypair()).collect();
+        let guardian_set = GuardianSet {
+            index: 0,
+            addresses: guardians.iter().map(|(_, addr)| *addr).collect(),
+        };
+        let keys: Vec<SigningKey> = guardians.into_iter().map(|(key, _)| key).collect();
+
+        let mut deps = mock_dependencies(20, &[]);
+        Config::from_storage(&mut deps.storage)
+            .set_guardian_set(&guardian_set)
+            .unwrap();
+        let governance_emitter = [7u8; 32];
+        write_bridge_contract(
+            &mut deps.storage,
+            GOVERNANCE_CHAIN_ID,
+            &CanonicalAddr::from(governance_emitter.to_vec()),
+        );
+
+        let chain_id_to_register: u16 = 5;
+        let emitter_to_register = [8u8; 32];
+        let mut payload = vec![2u8]; // RegisterBridgeContract
+        payload.extend_from_slice(&chain_id_to_register.to_be_bytes());
+        payload.extend_from_slice(&emitter_to_register);
+        let body = build_body(GOVERNANCE_CHAIN_ID, governance_emitter, 1, &payload);
+        let vaa = signed_vaa(&keys, &[0, 1, 2], &body);
+
+        try_submit_governance_action(&mut deps, mock_env("relayer", &[]), &vaa).unwrap();
+        assert_eq!(
+            read_bridge_contract(&deps.storage, chain_id_to_register),
+            Some(CanonicalAddr::from(emitter_to_register.to_vec()))
+        );
+
+        let err =
+            try_submit_governance_action(&mut deps, mock_env("relayer", &[]), &vaa).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("already executed")),
+            other => panic!("expected replay rejection, got {:?}", other),
+        }
+    }
+}
@@ -1,10 +1,16 @@
+pub mod bridge;
 pub mod contract;
+pub mod governance;
 pub mod msg;
 pub mod receiver;
 pub mod state;
 mod utils;
 mod viewing_key;
+pub mod wrapped_asset;
 
 #[cfg(target_arch = "wasm32")]
 mod wasm {
-    use s
\ No newline at end of file
+    use super::contract;
+
+    cosmwasm_std::create_entry_points_with_migration!(contract);
+}
\ No newline at end of file
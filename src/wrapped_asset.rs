@@ -0,0 +1,254 @@
+/// Wrapped-asset registry: lets this contract represent foreign tokens
+/// locally. The first sighting of a foreign asset instantiates a wrapped
+/// SNIP-20 for it; later sightings just refresh the cached metadata.
+use cosmwasm_std::{
+    log, to_binary, Api, Binary, CanonicalAddr, CosmosMsg, Env, Extern, HandleResponse, HumanAddr,
+    Querier, ReadonlyStorage, StdError, StdResult, Storage, WasmMsg,
+};
+use schemars::JsonSchema;
+use secret_toolkit::crypto::sha_256;
+use serde::{Deserialize, Serialize};
+
+use crate::governance::{take_bytes, take_u8, verify_vaa};
+use crate::state::{
+    is_transfer_completed, read_bridge_contract, read_wrapped_asset, read_wrapped_asset_meta,
+    set_transfer_completed, write_wrapped_asset, write_wrapped_asset_meta, Config,
+    WrappedAssetMeta,
+};
+
+/// Init message sent to the freshly instantiated wrapped-token contract,
+/// seeded with the metadata observed for the foreign asset plus everything
+/// a SNIP-20 `InitMsg` needs to stand up on its own (`admin`, `prng_seed`).
+/// `register_with`/`register_code_hash` and the `chain_id`/`foreign_address`
+/// pair let the wrapped token call `HandleMsg::RegisterWrappedAsset` back
+/// into this contract once it knows its own address — the same
+/// call-yourself-back pattern `tests/example-receiver` uses to register a
+/// SNIP-20 with a contract that only learns the SNIP-20's address after
+/// instantiating it.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct WrappedTokenInitMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub admin: HumanAddr,
+    pub prng_seed: Binary,
+    pub register_with: HumanAddr,
+    pub register_code_hash: String,
+    pub chain_id: u16,
+    pub foreign_address: Binary,
+}
+
+/// Handles `HandleMsg::AttestAsset`: verifies `vaa` against the stored
+/// guardian set the same way `governance::try_submit_governance_action`
+/// does, requires it to come from the trusted emitter registered for its
+/// `emitter_chain`, and rejects a replayed `(emitter_chain, sequence)` — an
+/// attestation is a cross-chain claim about foreign asset metadata, so it
+/// needs the same guardian authentication as any other message observed
+/// from a foreign chain. `code_id`/`code_hash` identify the wrapped-token
+/// code to instantiate and are local contract configuration, not something
+/// guardians attest to.
+///
+/// On first sighting of the attested `(chain_id, foreign_address)`,
+/// instantiates a wrapped token seeded with the given metadata and caches
+/// it; on later sightings, just refreshes the cached metadata.
+pub fn try_attest_asset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    vaa: &[u8],
+    code_id: u64,
+    code_hash: String,
+) -> StdResult<HandleResponse> {
+    let guardian_set = Config::from_storage(&mut deps.storage).guardian_set()?;
+    let body = verify_vaa(&guardian_set, vaa)?;
+
+    let chain_id = body.emitter_chain;
+    let trusted_emitter = read_bridge_contract(&deps.storage, chain_id).ok_or_else(|| {
+        StdError::generic_err(format!(
+            "no bridge contract registered for chain {}",
+            chain_id
+        ))
+    })?;
+    let claimed_emitter = CanonicalAddr::from(body.emitter_address.to_vec());
+    if claimed_emitter != trusted_emitter {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if is_transfer_completed(&deps.storage, chain_id, body.sequence) {
+        return Err(StdError::generic_err("attestation already processed"));
+    }
+    set_transfer_completed(&mut deps.storage, chain_id, body.sequence);
+
+    let mut pos = 0usize;
+    let mut foreign_address = [0u8; 32];
+    foreign_address.copy_from_slice(take_bytes(&body.payload, &mut pos, 32)?);
+    let mut name = [0u8; 32];
+    name.copy_from_slice(take_bytes(&body.payload, &mut pos, 32)?);
+    let mut symbol = [0u8; 32];
+    symbol.copy_from_slice(take_bytes(&body.payload, &mut pos, 32)?);
+    let decimals = take_u8(&body.payload, &mut pos)?;
+
+    let meta = WrappedAssetMeta {
+        name,
+        symbol,
+        decimals,
+    };
+    let previous_meta = read_wrapped_asset_meta(&deps.storage, chain_id, &foreign_address)?;
+    if previous_meta.as_ref() == Some(&meta) {
+        return Ok(HandleResponse {
+            messages: vec![],
+            log: vec![log("action", "attest_asset"), log("status", "unchanged")],
+            data: None,
+        });
+    }
+    write_wrapped_asset_meta(&mut deps.storage, chain_id, &foreign_address, &meta)?;
+
+    if read_wrapped_asset(&deps.storage, chain_id, &foreign_address).is_some() {
+        // Already wrapped; propagating the refreshed name/symbol onto the
+        // wrapped token itself is left to that contract's own update path.
+        return Ok(HandleResponse {
+            messages: vec![],
+            log: vec![
+                log("action", "attest_asset"),
+                log("status", "metadata_refreshed"),
+            ],
+            data: None,
+        });
+    }
+
+    let init_msg = WrappedTokenInitMsg {
+        name: decode_fixed_str(&name),
+        symbol: decode_fixed_str(&symbol),
+        decimals,
+        admin: env.contract.address.clone(),
+        prng_seed: Binary::from(sha_256(&[chain_id.to_be_bytes().as_slice(), &foreign_address].concat()).to_vec()),
+        register_with: env.contract.address.clone(),
+        register_code_hash: env.contract_code_hash.clone(),
+        chain_id,
+        foreign_address: Binary::from(foreign_address.to_vec()),
+    };
+    // Hex rather than `Binary`'s base64 `Display` impl: shorter-lived
+    // contract labels don't need to round-trip, just stay unique and
+    // human-readable in explorer output.
+    let label = format!("wrapped-{}-{}", chain_id, to_hex(&foreign_address));
+
+    Ok(HandleResponse {
+        messages: vec![CosmosMsg::Wasm(WasmMsg::Instantiate {
+            code_id,
+            msg: to_binary(&init_msg)?,
+            send: vec![],
+            label,
+            callback_code_hash: code_hash,
+        })],
+        log: vec![
+            log("action", "attest_asset"),
+            log("status", "create_wrapped"),
+        ],
+        data: None,
+    })
+}
+
+/// Handles `HandleMsg::RegisterWrappedAsset`: completes the registry entry
+/// `try_attest_asset` started. The wrapped token contract calls this back
+/// during its own `init`, echoing the `chain_id`/`foreign_address` it was
+/// seeded with; the address being registered is taken from
+/// `env.message.sender`, not a caller-supplied field, since trusting a
+/// caller-supplied address would let anyone repoint an existing wrapped
+/// asset at a token they control. Refuses to overwrite an
+/// already-registered mapping for the same reason.
+pub fn try_register_wrapped_asset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    chain_id: u16,
+    foreign_address: [u8; 32],
+) -> StdResult<HandleResponse> {
+    if read_wrapped_asset(&deps.storage, chain_id, &foreign_address).is_some() {
+        return Err(StdError::generic_err(
+            "wrapped asset already registered for this chain_id/foreign_address",
+        ));
+    }
+
+    let local_address = deps.api.canonical_address(&env.message.sender)?;
+    write_wrapped_asset(&mut deps.storage, chain_id, &foreign_address, &local_address);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("action", "register_wrapped_asset")],
+        data: None,
+    })
+}
+
+/// Handles `QueryMsg::WrappedAssetInfo`: returns the local wrapped-token
+/// address and cached metadata for a foreign asset, or `None` if no wrapped
+/// token has registered for it yet (including an attested asset whose
+/// wrapped token has not called `RegisterWrappedAsset` back).
+pub fn query_wrapped_asset_info<A: Api, S: ReadonlyStorage>(
+    api: &A,
+    storage: &S,
+    chain_id: u16,
+    foreign_address: [u8; 32],
+) -> StdResult<Option<(HumanAddr, WrappedAssetMeta)>> {
+    let local_address = match read_wrapped_asset(storage, chain_id, &foreign_address) {
+        Some(address) => address,
+        None => return Ok(None),
+    };
+    let meta = read_wrapped_asset_meta(storage, chain_id, &foreign_address)?
+        .ok_or_else(|| StdError::generic_err("wrapped asset is registered but missing its cached metadata"))?;
+
+    Ok(Some((api.human_address(&local_address)?, meta)))
+}
+
+fn decode_fixed_str(bytes: &[u8; 32]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(32);
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    #[test]
+    fn register_wrapped_asset_uses_caller_address_not_a_passed_in_one() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        try_register_wrapped_asset(&mut deps, mock_env("wrapped-token", &[]), 2, [1u8; 32])
+            .unwrap();
+
+        let expected = deps
+            .api
+            .canonical_address(&HumanAddr("wrapped-token".to_string()))
+            .unwrap();
+        assert_eq!(
+            read_wrapped_asset(&deps.storage, 2, &[1u8; 32]),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn register_wrapped_asset_rejects_overwriting_an_existing_mapping() {
+        let mut deps = mock_dependencies(20, &[]);
+        try_register_wrapped_asset(&mut deps, mock_env("wrapped-token", &[]), 2, [1u8; 32])
+            .unwrap();
+
+        let err =
+            try_register_wrapped_asset(&mut deps, mock_env("attacker-token", &[]), 2, [1u8; 32])
+                .unwrap_err();
+
+        match err {
+            StdError::GenericErr { msg, .. } => assert!(msg.contains("already registered")),
+            other => panic!("expected already-registered error, got {:?}", other),
+        }
+
+        let expected = deps
+            .api
+            .canonical_address(&HumanAddr("wrapped-token".to_string()))
+            .unwrap();
+        assert_eq!(
+            read_wrapped_asset(&deps.storage, 2, &[1u8; 32]),
+            Some(expected)
+        );
+    }
+}